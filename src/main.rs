@@ -1,5 +1,5 @@
-use std::collections::HashMap;
 use std::fmt::{self, Write as _};
+use std::ops::ControlFlow;
 use std::time::Duration;
 
 use anyhow::Result;
@@ -8,7 +8,8 @@ use arguably::ArgParser;
 const MD_URL: &str =
   "https://github.com/codecrafters-io/build-your-own-x/raw/refs/heads/master/README.md";
 
-use markdown::{mdast::Node, ParseOptions};
+use nanorand::{Rng, WyRand};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 use thiserror::Error as ThisError;
 
 #[derive(Debug, ThisError)]
@@ -29,12 +30,215 @@ async fn download_md() -> Result<String, RollError> {
     .or(Err(RollError::Fetch))
 }
 
+/// Where we are in the README while walking the event stream.
+///
+/// The README has no stable tree shape we can rely on (headings and lists
+/// just show up as a flat run of events), so instead of re-deriving an AST
+/// we track the minimal state needed to know what a given event means.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ParseState {
+  #[default]
+  Idle,
+  SawBuildHeading,
+  CollectingList,
+  InListItem,
+  InLink,
+}
+
+/// A single step of a markdown walk.
+///
+/// `pulldown-cmark` yields a flat stream of `Event`s rather than a node
+/// tree, so there's nothing to recurse into — but we still want the
+/// error-propagating contract of a markdown-it/comrak visitor: `Err`
+/// aborts the walk with a parse error, and `ControlFlow::Break` stops
+/// early without treating the rest of the document as missing (used here
+/// to stop at the "Contribute" heading). `visit` is called once per event,
+/// in document order, which is already equivalent to a depth-first walk.
+///
+/// `CategoryCollector` is the only implementer; `--list` and `--category`
+/// work on the categories it collects rather than walking the events again.
+trait Visitor {
+  fn visit(&mut self, event: &Event) -> Result<ControlFlow<()>, RollError>;
+}
+
+/// Drive `visitor` over every event in the flat `pulldown-cmark` stream,
+/// stopping as soon as it errors or asks to break.
+fn walk(parser: Parser, visitor: &mut impl Visitor) -> Result<(), RollError> {
+  for event in parser {
+    if visitor.visit(&event)?.is_break() {
+      break;
+    }
+  }
+  Ok(())
+}
+
+/// A project pulled from a README list item: its colorized title and the
+/// URL a `--open` roll should launch.
+#[derive(Debug, Clone)]
+struct Project {
+  title: String,
+  url: String,
+}
+
+/// A `#### Build Your Own X` section: its display name, a URL-safe slug
+/// for `--category`, and the projects listed under it.
+#[derive(Debug, Clone)]
+struct Category {
+  name: String,
+  slug: String,
+  projects: Vec<Project>,
+}
+
+/// Slugify a category name the way rustdoc/comrak slugify heading text for
+/// anchors: lowercase, runs of non-alphanumerics collapsed to a single
+/// hyphen, no leading/trailing hyphen.
+fn slugify(name: &str) -> String {
+  let mut slug = String::new();
+  let mut last_was_hyphen = true;
+  for ch in name.chars() {
+    if ch.is_ascii_alphanumeric() {
+      slug.push(ch.to_ascii_lowercase());
+      last_was_hyphen = false;
+    } else if !last_was_hyphen {
+      slug.push('-');
+      last_was_hyphen = true;
+    }
+  }
+  if slug.ends_with('-') {
+    slug.pop();
+  }
+  slug
+}
+
+/// Collects categories from the README event stream, stopping once the
+/// "Contribute" section is reached.
+///
+/// Categories are kept in a `Vec`, in the order the README lists them,
+/// rather than a `HashMap` — a `HashMap`'s iteration order is randomized
+/// per process, which would make `--seed`'s category roll pick a
+/// different category every run even with the RNG held fixed.
+#[derive(Debug, Default)]
+struct CategoryCollector {
+  categories: Vec<Category>,
+  state: ParseState,
+  in_h2: bool,
+  in_h4: bool,
+  current_category: Option<usize>,
+  link_title: String,
+  link_url: String,
+}
+
+impl Visitor for CategoryCollector {
+  fn visit(&mut self, event: &Event) -> Result<ControlFlow<()>, RollError> {
+    match event {
+      Event::Start(Tag::Heading {
+        level: HeadingLevel::H2,
+        ..
+      }) => self.in_h2 = true,
+      Event::End(TagEnd::Heading(HeadingLevel::H2)) => self.in_h2 = false,
+      Event::Start(Tag::Heading {
+        level: HeadingLevel::H4,
+        ..
+      }) => self.in_h4 = true,
+      Event::End(TagEnd::Heading(HeadingLevel::H4)) => {
+        self.in_h4 = false;
+        self.state = ParseState::Idle;
+      }
+      Event::Text(t) if self.in_h2 && t.starts_with("Contribute") => {
+        return Ok(ControlFlow::Break(()));
+      }
+      Event::Text(t) if self.in_h4 && t.starts_with("Build") => {
+        self.state = ParseState::SawBuildHeading;
+      }
+      Event::Code(code) if self.state == ParseState::SawBuildHeading => {
+        let slug = slugify(code);
+        self.categories.push(Category {
+          name: code.to_string(),
+          slug: slug.clone(),
+          projects: vec![],
+        });
+        self.current_category = Some(self.categories.len() - 1);
+        self.state = ParseState::Idle;
+      }
+      Event::Start(Tag::List(_)) if self.current_category.is_some() => {
+        self.state = ParseState::CollectingList;
+      }
+      Event::End(TagEnd::List(_)) if self.state == ParseState::CollectingList => {
+        self.state = ParseState::Idle;
+      }
+      Event::Start(Tag::Item) if self.state == ParseState::CollectingList => {
+        self.state = ParseState::InListItem;
+      }
+      Event::End(TagEnd::Item) if self.state == ParseState::InListItem => {
+        self.state = ParseState::CollectingList;
+      }
+      Event::Start(Tag::Link { dest_url, .. }) if self.state == ParseState::InListItem => {
+        self.link_title.clear();
+        self.link_url = dest_url.to_string();
+        self.state = ParseState::InLink;
+      }
+      Event::End(TagEnd::Link) if self.state == ParseState::InLink => {
+        if let Some(idx) = self.current_category {
+          self.categories[idx].projects.push(Project {
+            title: self.link_title.clone(),
+            url: self.link_url.clone(),
+          });
+        }
+        self.state = ParseState::InListItem;
+      }
+      Event::Start(Tag::Strong) if self.state == ParseState::InLink => {
+        write!(&mut self.link_title, "{{blue}}{{bold}}").or(Err(RollError::Parse))?;
+      }
+      Event::End(TagEnd::Strong) if self.state == ParseState::InLink => {
+        write!(&mut self.link_title, "{{-}}").or(Err(RollError::Parse))?;
+      }
+      Event::Start(Tag::Emphasis) if self.state == ParseState::InLink => {
+        write!(&mut self.link_title, "{{white}}{{italic}}").or(Err(RollError::Parse))?;
+      }
+      Event::End(TagEnd::Emphasis) if self.state == ParseState::InLink => {
+        write!(&mut self.link_title, "{{-}}").or(Err(RollError::Parse))?;
+      }
+      Event::Start(Tag::Strikethrough) if self.state == ParseState::InLink => {
+        write!(&mut self.link_title, "{{strikethrough}}").or(Err(RollError::Parse))?;
+      }
+      Event::End(TagEnd::Strikethrough) if self.state == ParseState::InLink => {
+        write!(&mut self.link_title, "{{-}}").or(Err(RollError::Parse))?;
+      }
+      Event::Code(code) if self.state == ParseState::InLink => {
+        write!(&mut self.link_title, "{{dim}}`{code}`{{-}}").or(Err(RollError::Parse))?;
+      }
+      Event::Text(t) if self.state == ParseState::InLink => {
+        write!(&mut self.link_title, "{t}").or(Err(RollError::Parse))?;
+      }
+      _ => {}
+    }
+
+    Ok(ControlFlow::Continue(()))
+  }
+}
+
+/// Parse the `build-your-own-x` README into the categories it lists, in
+/// README order.
+fn extract_categories(md_text: &str) -> Result<Vec<Category>, RollError> {
+  let options = Options::ENABLE_TABLES
+    | Options::ENABLE_STRIKETHROUGH
+    | Options::ENABLE_FOOTNOTES
+    | Options::ENABLE_SMART_PUNCTUATION;
+
+  let mut collector = CategoryCollector::default();
+  walk(Parser::new_ext(md_text, options), &mut collector)?;
+  Ok(collector.categories)
+}
+
 /// Roll a dn where n -> # of sides on die
 /// e.g. roll_die(6) rolls a d6
-fn roll_die<S: AsRef<str> + fmt::Display>(n: usize, msg: S, hide_spinner: bool) -> usize {
-  use nanorand::{Rng, WyRand};
+fn roll_die<S: AsRef<str> + fmt::Display>(
+  rng: &mut WyRand,
+  n: usize,
+  msg: S,
+  hide_spinner: bool,
+) -> usize {
   use spinners::{Spinner, Spinners};
-  let mut rng = WyRand::new();
 
   if !hide_spinner {
     let mut spin = Spinner::new(Spinners::Dots, format!("{msg} (d{n})"));
@@ -45,113 +249,147 @@ fn roll_die<S: AsRef<str> + fmt::Display>(n: usize, msg: S, hide_spinner: bool)
   rng.generate_range(0..n)
 }
 
+/// Print an error for an unrecognized `--category` value (whether rolling or
+/// listing with `--list`) along with every category we actually parsed, so
+/// the user can correct it.
+fn print_unknown_category(categories: &[Category], query: &str) {
+  println!(
+    "{}",
+    tempera::colorize_template(&format!("{{red}}[!] unknown category \"{query}\"{{-}}, valid categories:"))
+  );
+  for category in categories {
+    println!("  - {} ({})", category.name, category.slug);
+  }
+}
+
+/// Resolve a `--category` value (a slug or a display name) to the `Category`
+/// it names. `--list` takes no value of its own: `--list --category <name>`
+/// lists one category's projects, `--list` alone lists the categories.
+///
+/// Tries an exact slug match first, then falls back to a fuzzy match: the
+/// query slug as a substring of a category's slug, or the raw query as a
+/// substring of its display name (case-insensitive). That lets `--category
+/// react`, `--category "React Clone"`, and `--category react-clone` all land
+/// on a category named `React Clone` without the caller having to know the
+/// exact slug.
+fn find_category<'a>(categories: &'a [Category], query: &str) -> Option<&'a Category> {
+  let query_slug = slugify(query);
+  if let Some(category) = categories.iter().find(|c| c.slug == query_slug) {
+    return Some(category);
+  }
+
+  let query_lower = query.to_lowercase();
+  categories
+    .iter()
+    .find(|c| c.slug.contains(&query_slug) || c.name.to_lowercase().contains(&query_lower))
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
   let mut parser = ArgParser::new()
     .helptext("Usage: rollthetech ...")
     .version("0.1")
-    .flag("fast f");
+    .flag("fast f")
+    .flag("open o")
+    .option("category c", "")
+    .flag("list l")
+    .option("seed s", "");
 
   if let Err(err) = parser.parse() {
     err.exit();
   }
 
+  let seed: u64 = if parser.found("seed") {
+    parser
+      .value("seed")
+      .parse()
+      .map_err(|_| anyhow::anyhow!("--seed expects an integer"))?
+  } else {
+    WyRand::new().generate()
+  };
+  let mut rng = WyRand::new_seed(seed);
+  if !parser.found("seed") {
+    println!(
+      "{}",
+      tempera::colorize_template(&format!("{{dim}}seed: {seed}{{-}}"))
+    );
+  }
+
   let md_text = download_md().await?;
-  let ast = markdown::to_mdast(&md_text, &ParseOptions::default()).or(Err(RollError::Parse))?;
-
-  let mut categories: HashMap<String, Vec<String>> = HashMap::new();
-  if let Node::Root(root) = ast {
-    let mut current_category: Option<String> = None;
-    for child in &root.children {
-      match child {
-        Node::Heading(h) if h.depth == 2 => {
-          if let Some(Node::Text(t)) = h.children.first()
-            && t.value.starts_with("Contribute")
-          {
-            break;
-          }
-        }
-        Node::Heading(h) if h.depth == 4 => {
-          let Some(Node::Text(t)) = h.children.first() else {
-            println!("[!] expected direct text w/ depth 4");
-            Err(RollError::Parse)?
-          };
-
-          if t.value.starts_with("Build") {
-            if let Node::InlineCode(ic) = &h.children[1] {
-              current_category = Some(ic.value.clone());
-              categories.insert(ic.value.clone(), vec![]);
-            } else {
-              println!("[!] expected inline code in heading w/ depth 4");
-              Err(RollError::Parse)?
-            }
-          }
-        }
-        Node::List(l) => {
-          if let Some(cc) = &current_category
-            && !cc.is_empty()
-          {
-            for item in &l.children {
-              let Node::Link(lnk) = item
-                .children()
-                .unwrap()
-                .first()
-                .unwrap() // Paragraph
-                .children()
-                .unwrap()
-                .first()
-                .unwrap()
-              // Link
-              else {
-                println!("[!] expected link for category item");
-                Err(RollError::Parse)?
-              };
-
-              let mut link_title = String::new();
-              for link_child in &lnk.children {
-                match link_child {
-                  Node::Strong(s) => {
-                    let Node::Text(stxt) = &s.children[0] else {
-                      Err(RollError::Parse)?
-                    };
-                    write!(&mut link_title, "{{blue}}{{bold}}{}{{-}}: ", stxt.value)?;
-                  }
-                  Node::Emphasis(e) => {
-                    let Node::Text(etxt) = &e.children[0] else {
-                      Err(RollError::Parse)?
-                    };
-                    write!(&mut link_title, "{{white}}{{italic}}{}{{-}}", etxt.value)?;
-                  }
-                  _ => {}
-                }
-              }
-              categories
-                .get_mut(current_category.as_ref().unwrap())
-                .unwrap()
-                .push(link_title);
-            }
-          }
-        }
-        _ => {}
+  let categories = extract_categories(&md_text)?;
+  if categories.is_empty() {
+    println!(
+      "{}",
+      tempera::colorize_template("{red}[!] no categories found in the README{-}")
+    );
+    return Ok(());
+  }
+
+  if parser.found("list") {
+    if parser.found("category") {
+      let query = parser.value("category");
+      let Some(category) = find_category(&categories, &query) else {
+        print_unknown_category(&categories, &query);
+        return Ok(());
+      };
+      for project in &category.projects {
+        println!("{}", tempera::colorize_template(&project.title));
+      }
+    } else {
+      let mut sorted: Vec<&Category> = categories.iter().collect();
+      sorted.sort_by(|a, b| a.slug.cmp(&b.slug));
+      for category in sorted {
+        let count = category.projects.len();
+        println!(
+          "{}",
+          tempera::colorize_template(&format!(
+            "{{blue}}{{bold}}{}{{-}} ({count} project{})",
+            category.name,
+            if count == 1 { "" } else { "s" }
+          ))
+        );
       }
     }
+    return Ok(());
   }
 
-  let category_idx = roll_die(
-    categories.keys().len(),
-    "Deciding a category... ",
-    parser.found("fast"),
-  );
-  let category = categories.keys().nth(category_idx).unwrap().as_str();
+  let category = if parser.found("category") {
+    let query = parser.value("category");
+    let Some(category) = find_category(&categories, &query) else {
+      print_unknown_category(&categories, &query);
+      return Ok(());
+    };
+    category
+  } else {
+    let category_idx = roll_die(
+      &mut rng,
+      categories.len(),
+      "Deciding a category... ",
+      parser.found("fast"),
+    );
+    &categories[category_idx]
+  };
   if parser.found("fast") {
     println!(
       "{}",
-      tempera::colorize_template(&format!(" â†’ {{bold}}{{italic}}{category}{{-}}"))
+      tempera::colorize_template(&format!(" â†’ {{bold}}{{italic}}{}{{-}}", category.name))
     )
   };
 
-  let projects = &categories[category];
+  if category.projects.is_empty() {
+    println!(
+      "{}",
+      tempera::colorize_template(&format!(
+        "{{red}}[!] category \"{}\" has no projects{{-}}",
+        category.name
+      ))
+    );
+    return Ok(());
+  }
+
+  let projects = &category.projects;
   let project_idx = roll_die(
+    &mut rng,
     projects.len(),
     "Deciding a project...",
     parser.found("fast"),
@@ -160,7 +398,16 @@ async fn main() -> Result<()> {
     println!();
   }
 
-  println!("{}", tempera::colorize_template(&projects[project_idx]));
+  let project = &projects[project_idx];
+  println!("{}", tempera::colorize_template(&project.title));
+  println!(
+    "{}",
+    tempera::colorize_template(&format!("{{dim}}{}{{-}}", project.url))
+  );
+
+  if parser.found("open") {
+    open::that(&project.url)?;
+  }
 
   Ok(())
 }